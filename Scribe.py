@@ -20,10 +20,13 @@ class TranscriberApp:
         root.title("Scribe")
         root.state("zoomed")
         root.configure(bg="#A3A9B7")
+        root.protocol("WM_DELETE_WINDOW", self.on_close)
         self.audio_device = "Microphone Array (Realtek(R) Audio)"
         self.fs = 44100
         self.duration = 7200
-        self.output_directory = os.path.join(os.path.expanduser("~"), "Documents", "Scribe", "recordings")
+        self.output_directory = os.path.join(self.data_directory(), "recordings")
+        portable_directory = self.portable_directory()
+        self.models_directory = os.path.join(portable_directory, "models") if portable_directory else None
         if not os.path.exists(self.output_directory):
             os.makedirs(self.output_directory)
         
@@ -31,6 +34,8 @@ class TranscriberApp:
         self.base_filename = "Scribe_Recording"
         self.start_time = None
         self.process = None
+        self.ffmpeg_process = None
+        self.transcription_process = None
         self.output_path = None
 
         for i in range(5):
@@ -46,7 +51,7 @@ class TranscriberApp:
         
         self.status_var = tk.StringVar(value=f"Your recordings will be at: {self.output_directory}")
         self.record_btn_text = tk.StringVar(value="Start new recording")
-        self.upload_btn_text = tk.StringVar(value="Upload an mp3 file")      
+        self.upload_btn_text = tk.StringVar(value="Upload an audio file")      
         self.transcribe_btn_text = tk.StringVar(value="Transcribe Audio")
 
         
@@ -56,6 +61,9 @@ class TranscriberApp:
         self.upload_btn = tk.Button(root, textvariable=self.upload_btn_text, command=self.upload_audio, width=20)
         self.upload_btn.grid(row=2, column=4, ipadx=20, ipady=5)
 
+        self.open_folder_btn = tk.Button(root, text="Open recordings folder", command=self.open_recordings_folder, width=20)
+        self.open_folder_btn.grid(row=3, column=4, ipadx=20, ipady=5, sticky="n")
+
         self.transcribe_btn = tk.Button(root, textvariable=self.transcribe_btn_text, command=lambda: self.transcribe_audio(self.transcription_path), width=20)
         self.transcribe_btn.grid(row=2, column=2, ipadx=20, ipady=5)
         self.transcribe_btn.config(state=tk.DISABLED)
@@ -71,14 +79,28 @@ class TranscriberApp:
         self.transcript_box.grid(row=4, column=0, columnspan=5, pady=10)
         self.transcript_box.config(state=tk.DISABLED)
 
+    def portable_directory(self):
+        # Portable mode: SCRIBE_DATA_DIR, or a portable.txt next to the app, keeps recordings and models out of the user profile
+        if os.environ.get("SCRIBE_DATA_DIR"):
+            return os.environ["SCRIBE_DATA_DIR"]
+        app_directory = os.path.dirname(os.path.abspath(__file__))
+        if os.path.exists(os.path.join(app_directory, "portable.txt")):
+            return app_directory
+        return None
+
+    def data_directory(self):
+        return self.portable_directory() or os.path.join(os.path.expanduser("~"), "Documents", "Scribe")
+
     def initialize_variables(self):
         self.isRecording = False
         self.isTranscribing = False
         self.transcription_path = None
+        self.detected_language = None
+        self.closing = False
 
     def upload_audio(self):
         try:
-            audio_path = filedialog.askopenfilename(title="Select Audio file", filetypes=[("Audio files", "*.mp3;*.wav")])
+            audio_path = filedialog.askopenfilename(title="Select Audio file", filetypes=[("Audio files", "*.mp3;*.wav;*.m4a;*.ogg;*.flac")])
             if audio_path:
                 self.transcription_path = audio_path
                 self.output_path = audio_path
@@ -89,10 +111,18 @@ class TranscriberApp:
         except Exception as e:
             self.status_var.set(f"Error: {e}")
 
+    def open_recordings_folder(self):
+        try:
+            os.makedirs(self.output_directory, exist_ok=True)
+            os.startfile(self.output_directory)
+        except Exception as e:
+            self.status_var.set(f"Error: {e}")
+
 
     def transcribe_audio(self, audio_path):
         if not self.isTranscribing and audio_path:
             self.isTranscribing = True
+            self.detected_language = None
             self.q = queue.Queue()
             self.transcribing_thread = threading.Thread(target=self._transcribe_audio, args=(audio_path,), daemon=True)
             self.transcribing_thread.start()
@@ -104,22 +134,34 @@ class TranscriberApp:
     def _transcribe_audio(self, audio_path):
         try:
             if audio_path:
+                if self.closing:
+                    return
                 self.status_var.set("Transcription in progress...")
-                self.process = subprocess.Popen(
-                    ["python", "transcribe.py", audio_path],
+                command = ["python", "transcribe.py", audio_path]
+                if self.models_directory:
+                    command += ["--download_root", self.models_directory]
+                self.transcription_process = subprocess.Popen(
+                    command,
                     stdout=subprocess.PIPE,
                     stderr=subprocess.PIPE,
                     text=True
                 )
-                for line in iter(self.process.stdout.readline, ''):
+                # The window may have been closed before transcribe.py was spawned
+                if self.closing:
+                    self.transcription_process.terminate()
+                for line in iter(self.transcription_process.stdout.readline, ''):
                     self.q.put(line)
-                self.process.stdout.close()
-                self.process.wait()
+                self.transcription_process.stdout.close()
+                self.transcription_process.wait()
+                if self.closing:
+                    return
                 self.update_gui("Transcription task ended.")
                 self.reset_ui_after_transcription()  # Reset UI here
             else:
                 self.status_var.set("No valid audio file selected.")
         except Exception as e:
+            if self.closing:
+                return
             self.update_gui(f"Transcription failed: {e}")
             self.reset_ui_after_transcription()  # Reset UI here if exception occurs
             self.isTranscribing = False
@@ -134,6 +176,9 @@ class TranscriberApp:
             if "PROGRESS:" in line:
                 progress_value = float(line.split(":")[1])
                 self.progress['value'] = progress_value
+            elif line.startswith("LANGUAGE:"):
+                _, language, probability = line.strip().split(":")
+                self.detected_language = f"{language} ({float(probability):.0%})"
             else:
                 self.transcript_box.insert(tk.END, line)
                 self.transcript_box.see(tk.END)
@@ -167,6 +212,23 @@ class TranscriberApp:
             stdout=subprocess.PIPE, 
             stderr=subprocess.PIPE, text=True)
         self.ffmpeg_process = self.process
+        # The window may have been closed before ffmpeg was spawned
+        if self.closing:
+            self.ffmpeg_process.kill()
+            return
+
+        # ffmpeg exits straight away when the capture device is missing
+        try:
+            self.ffmpeg_process.wait(timeout=1)
+        except subprocess.TimeoutExpired:
+            return
+        if self.closing:
+            return
+        self.process = None
+        self.start_time = None
+        self.isRecording = False
+        self.record_btn_text.set("Start new recording")
+        self.update_gui(f"No microphone detected ({self.audio_device}). Check that it is connected and try again.")
 
 
     def stop_recording(self):
@@ -177,8 +239,12 @@ class TranscriberApp:
                 self.process.wait()
                 self.process = None
                 self.start_time = None
+                if self.closing:
+                    return
                 self.transcribe_audio(self.output_path)
         except Exception as e:
+            if self.closing:
+                return
             self.update_gui(f"Recording failed: {e}")
         self.transcription_path = self.output_path
             
@@ -246,9 +312,29 @@ class TranscriberApp:
         self.upload_btn.config(state=tk.NORMAL)
         self.record_btn.config(state=tk.NORMAL if not self.isRecording else tk.DISABLED)
         self.transcription_path = None
-        self.status_var.set("Transcription complete. Start a new recording or upload an audio file.")
+        if self.detected_language:
+            self.status_var.set(f"Transcription complete (detected language: {self.detected_language}). Start a new recording or upload an audio file.")
+        else:
+            self.status_var.set("Transcription complete. Start a new recording or upload an audio file.")
         self.progress['value'] = 0
 
+    def on_close(self):
+        # Don't leave ffmpeg recording or transcribe.py running after the window is gone
+        self.closing = True
+        if self.ffmpeg_process and self.ffmpeg_process.poll() is None:
+            try:
+                self.ffmpeg_process.stdin.write('q')
+                self.ffmpeg_process.stdin.flush()
+                self.ffmpeg_process.wait(timeout=5)
+            except Exception:
+                self.ffmpeg_process.kill()
+        if self.transcription_process and self.transcription_process.poll() is None:
+            try:
+                self.transcription_process.terminate()
+            except Exception:
+                self.transcription_process.kill()
+        self.root.destroy()
+
         
 if __name__ == "__main__":
     root = tk.Tk()
@@ -15,14 +15,14 @@ except ImportError:
 
 def initialize_model(args):
     try:
-        return WhisperModel(args.model, device=args.device, compute_type=args.compute_type)
+        return WhisperModel(args.model, device=args.device, compute_type=args.compute_type, download_root=args.download_root)
     except Exception as e:
         logging.error(f"Failed to initialize the model: {e}")
         sys.exit(1)
 
-def transcribe_audio(model, audio_file, beam_size):
+def transcribe_audio(model, audio_file, beam_size, initial_prompt=None, language=None):
     try:
-        return model.transcribe(audio_file, beam_size=beam_size)
+        return model.transcribe(audio_file, beam_size=beam_size, initial_prompt=(initial_prompt or "").strip() or None, language=language)
     except Exception as e:
         logging.error(f"Failed to transcribe audio: {e}")
         sys.exit(1)
@@ -43,25 +43,73 @@ def print_transcription(segments, chunk_size):
         print(''.join(chunk), end='')
         print("PROGRESS:100")
 
+def format_timestamp(seconds, separator):
+    milliseconds = int(round(seconds * 1000))
+    hours, milliseconds = divmod(milliseconds, 3600000)
+    minutes, milliseconds = divmod(milliseconds, 60000)
+    seconds, milliseconds = divmod(milliseconds, 1000)
+    return f"{hours:02d}:{minutes:02d}:{seconds:02d}{separator}{milliseconds:03d}"
+
+def write_subtitles(segments, subtitle_format, subtitle_path):
+    separator = "," if subtitle_format == "srt" else "."
+    cues = []
+    for segment in segments:
+        text = segment.text.strip()
+        if not text:
+            continue
+        start = format_timestamp(segment.start, separator)
+        end = format_timestamp(max(segment.end, segment.start), separator)
+        cue = f"{start} --> {end}\n{text}\n"
+        if subtitle_format == "srt":
+            cue = f"{len(cues) + 1}\n{cue}"
+        cues.append(cue)
+
+    header = "WEBVTT\n\n" if subtitle_format == "vtt" else ""
+    try:
+        with open(subtitle_path, "w", encoding="utf-8") as f:
+            f.write(header + "\n".join(cues))
+    except OSError as e:
+        logging.error(f"Failed to write subtitles: {e}")
+        sys.exit(1)
+    logging.info(f"Subtitles written to {subtitle_path}")
+
 def main():
     logging.basicConfig(level=logging.INFO)
     parser = argparse.ArgumentParser(description="Transcribe audio files.")
     parser.add_argument("audio_file", help="Path to the audio file to transcribe.")
     parser.add_argument("--model", default="medium", help="Whisper model to use.")
+    parser.add_argument("--download_root", help="Directory to download and cache models in. Defaults to the Hugging Face cache.")
     parser.add_argument("--device", default="cuda", choices=["cuda", "cpu"], help="Computation device.")
     parser.add_argument("--compute_type", default="float16", choices=["float16", "int8_float16", "int8"], help="Compute type.")
     parser.add_argument("--chunk_size", type=int, default=5, help="Number of segments per chunk")
     parser.add_argument("--beam_size", type=int, default=5, help="Beam size for transcription.")
+    parser.add_argument("--language", help="Language code of the audio, e.g. 'en'. Detected automatically when omitted.")
+    parser.add_argument("--initial_prompt", default="", help="Text to bias decoding toward names and jargon. Long prompts are truncated by Whisper.")
+    parser.add_argument("--subtitle_format", choices=["srt", "vtt"], help="Also export the transcript as subtitles.")
+    parser.add_argument("--subtitle_path", help="Subtitle output path. Defaults to the audio file path with the subtitle extension.")
     args = parser.parse_args()
 
     if not os.path.exists(args.audio_file):
         logging.error(f"Audio file {args.audio_file} not found.")
         sys.exit(1)
 
+    if args.language and args.language != "en" and args.model.endswith(".en"):
+        multilingual_model = args.model[:-3]
+        suggestion = f" such as {multilingual_model}" if multilingual_model in ("tiny", "base", "small", "medium") else ""
+        logging.error(f"Model {args.model} only supports English. Use a multilingual model{suggestion} for language '{args.language}'.")
+        sys.exit(1)
+
     model = initialize_model(args)
-    segments_list = list(transcribe_audio(model, args.audio_file, args.beam_size)[0])
+    segments, info = transcribe_audio(model, args.audio_file, args.beam_size, args.initial_prompt, args.language)
+    if not args.language:
+        print(f"LANGUAGE:{info.language}:{info.language_probability:.2f}", flush=True)
+    segments_list = list(segments)
     total_segments = len(segments_list)
     print_transcription(segments_list, args.chunk_size)
 
+    if args.subtitle_format:
+        subtitle_path = args.subtitle_path or f"{os.path.splitext(args.audio_file)[0]}.{args.subtitle_format}"
+        write_subtitles(segments_list, args.subtitle_format, subtitle_path)
+
 if __name__ == "__main__":
     main()